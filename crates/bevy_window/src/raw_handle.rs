@@ -4,7 +4,15 @@ use bevy_ecs::prelude::Component;
 use raw_window_handle::{
     DisplayHandle, HandleError, HasDisplayHandle, HasWindowHandle, WindowHandle,
 };
-use std::{fmt, ops::Deref, sync::Arc};
+use std::{
+    fmt,
+    ops::Deref,
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        Arc, Weak,
+    },
+    thread::ThreadId,
+};
 
 /// A wrapper over a window.
 ///
@@ -38,6 +46,30 @@ impl<W: 'static> Deref for WindowWrapper<W> {
 trait WindowTrait: HasWindowHandle + HasDisplayHandle {}
 impl<T: HasWindowHandle + HasDisplayHandle> WindowTrait for T {}
 
+/// The application-lifecycle state of the native window/surface backing a [`RawHandleWrapper`].
+///
+/// On some platforms (most notably Android) the OS destroys the underlying native window
+/// whenever the application is suspended, and only recreates it once the application is
+/// resumed. Handing out a handle while [`Suspended`](HandleState::Suspended) would let callers
+/// draw to (or otherwise touch) a surface that no longer exists, so [`RawHandleWrapper`] tracks
+/// this state and refuses to do so.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HandleState {
+    /// The native window is live and its handle is safe to use.
+    Active = 0,
+    /// The application has been suspended; the native window may have been destroyed.
+    Suspended = 1,
+}
+
+// Whether the current platform restricts window handle access to the thread the window was
+// created on. Where this is `false`, `RawHandleWrapper::try_get_handle` doesn't need to check
+// the calling thread at all, since access is statically known-valid from any thread.
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+const PLATFORM_REQUIRES_MAIN_THREAD: bool = true;
+#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+const PLATFORM_REQUIRES_MAIN_THREAD: bool = false;
+
 /// A wrapper over [`HasWindowHandle`] and [`HasDisplayHandle`] that allows us to safely pass it across threads.
 ///
 /// Depending on the platform, the underlying pointer-containing handle cannot be used on all threads,
@@ -46,6 +78,12 @@ impl<T: HasWindowHandle + HasDisplayHandle> WindowTrait for T {}
 #[derive(Clone, Component)]
 pub struct RawHandleWrapper {
     window: Arc<dyn WindowTrait>,
+    // Shared so that every clone of this `RawHandleWrapper` (e.g. the one picked up by the
+    // renderer during extraction) observes suspend/resume at the same time as the original.
+    state: Arc<AtomicU8>,
+    // The thread this wrapper was constructed on, i.e. the window's owning thread. Used by
+    // `try_get_handle` to statically determine whether the current thread is known-valid.
+    thread_id: ThreadId,
 }
 
 impl fmt::Debug for RawHandleWrapper {
@@ -61,6 +99,8 @@ impl RawHandleWrapper {
     ) -> Result<RawHandleWrapper, HandleError> {
         Ok(RawHandleWrapper {
             window: window.reference.clone(),
+            state: Arc::new(AtomicU8::new(HandleState::Active as u8)),
+            thread_id: std::thread::current().id(),
         })
     }
 
@@ -73,6 +113,62 @@ impl RawHandleWrapper {
     pub unsafe fn get_handle(&self) -> ThreadLockedRawWindowHandleWrapper {
         ThreadLockedRawWindowHandleWrapper(self.clone())
     }
+
+    /// Returns a [`HasWindowHandle`] + [`HasDisplayHandle`] impl if the current thread is
+    /// statically known to be a valid context to use it from, avoiding the need for the
+    /// caller to reach for [`get_handle`](Self::get_handle)'s `unsafe` escape hatch.
+    ///
+    /// This is the case when either the calling thread is the same thread the window was
+    /// created on, or the current platform doesn't restrict window operations to a particular
+    /// thread in the first place. Otherwise, this returns `Err(HandleError::Unavailable)`.
+    pub fn try_get_handle(&self) -> Result<ThreadLockedRawWindowHandleWrapper, HandleError> {
+        if PLATFORM_REQUIRES_MAIN_THREAD && std::thread::current().id() != self.thread_id {
+            return Err(HandleError::Unavailable);
+        }
+        // SAFETY: we've just checked that either the platform imposes no thread restriction, or
+        // the current thread is the one the window (and therefore this wrapper) was created on.
+        Ok(unsafe { self.get_handle() })
+    }
+
+    /// Marks the window's handle as suspended, e.g. in response to an Android `Suspended`
+    /// lifecycle event.
+    ///
+    /// While suspended, [`ThreadLockedRawWindowHandleWrapper::window_handle`] and
+    /// [`ThreadLockedRawWindowHandleWrapper::display_handle`] return
+    /// `Err(HandleError::Unavailable)` instead of a handle to the (possibly destroyed) native
+    /// window, so in-flight frames don't get drawn to a dead surface.
+    pub fn set_suspended(&self) {
+        self.state
+            .store(HandleState::Suspended as u8, Ordering::SeqCst);
+    }
+
+    /// Marks the window's handle as active again, e.g. in response to an Android `Resumed`
+    /// lifecycle event.
+    ///
+    /// The handle itself is unchanged; the underlying window is expected to hand back its
+    /// recreated native window the next time its handle is fetched.
+    pub fn set_active(&self) {
+        self.state
+            .store(HandleState::Active as u8, Ordering::SeqCst);
+    }
+
+    fn is_active(&self) -> bool {
+        self.state.load(Ordering::SeqCst) == HandleState::Active as u8
+    }
+
+    /// Creates a [`WeakRawHandleWrapper`] that doesn't keep the underlying window alive.
+    ///
+    /// Holding a [`RawHandleWrapper`] keeps the OS window alive for as long as the wrapper is
+    /// held, even after the window has otherwise been despawned. A [`WeakRawHandleWrapper`]
+    /// instead lets a caller (e.g. the render world) notice that the window is gone and tear
+    /// down the surface, rather than extending its lifetime indefinitely.
+    pub fn downgrade(&self) -> WeakRawHandleWrapper {
+        WeakRawHandleWrapper {
+            window: Arc::downgrade(&self.window),
+            state: self.state.clone(),
+            thread_id: self.thread_id,
+        }
+    }
 }
 
 // SAFETY: [`RawHandleWrapper`] is just a normal "raw pointer", which doesn't impl Send/Sync. However the pointer is only
@@ -84,6 +180,50 @@ unsafe impl Send for RawHandleWrapper {}
 // SAFETY: This is safe for the same reasons as the Send impl above.
 unsafe impl Sync for RawHandleWrapper {}
 
+/// A weak version of [`RawHandleWrapper`], obtained via [`RawHandleWrapper::downgrade`].
+///
+/// This holds a [`Weak`] reference to the window rather than a strong [`Arc`], so holding one
+/// doesn't keep the OS window (and the surface drawing to it) alive past the window's real
+/// lifetime. Use [`try_get_handle`](Self::try_get_handle) to get a usable handle; it fails once
+/// the window has been dropped, which callers can treat as a signal to tear down any resources
+/// tied to that window.
+#[derive(Clone, Component)]
+pub struct WeakRawHandleWrapper {
+    window: Weak<dyn WindowTrait>,
+    state: Arc<AtomicU8>,
+    thread_id: ThreadId,
+}
+
+impl fmt::Debug for WeakRawHandleWrapper {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("WeakRawHandleWrapper")
+            .finish_non_exhaustive()
+    }
+}
+
+impl WeakRawHandleWrapper {
+    /// Returns a [`HasWindowHandle`] + [`HasDisplayHandle`] impl if the window is still alive
+    /// and the current thread is a statically known-valid context to use it from.
+    ///
+    /// This upgrades the underlying weak reference first, returning
+    /// `Err(HandleError::Unavailable)` if the window has already been dropped, before delegating
+    /// to the same thread check as [`RawHandleWrapper::try_get_handle`].
+    pub fn try_get_handle(&self) -> Result<ThreadLockedRawWindowHandleWrapper, HandleError> {
+        let window = self.window.upgrade().ok_or(HandleError::Unavailable)?;
+        RawHandleWrapper {
+            window,
+            state: self.state.clone(),
+            thread_id: self.thread_id,
+        }
+        .try_get_handle()
+    }
+}
+
+// SAFETY: see the corresponding impls for `RawHandleWrapper` above; the same reasoning applies.
+unsafe impl Send for WeakRawHandleWrapper {}
+// SAFETY: see the corresponding impls for `RawHandleWrapper` above; the same reasoning applies.
+unsafe impl Sync for WeakRawHandleWrapper {}
+
 /// A [`RawHandleWrapper`] that cannot be sent across threads.
 ///
 /// This safely exposes [`RawWindowHandle`] and [`RawDisplayHandle`], but care must be taken to ensure that the construction itself is correct.
@@ -101,6 +241,9 @@ impl HasWindowHandle for ThreadLockedRawWindowHandleWrapper {
         // as the `raw_window_handle` method is safe. We cannot guarantee that all calls
         // of this method are correct (as it may be off the main thread on an incompatible platform),
         // and so exposing a safe method to get a [`RawWindowHandle`] directly would be UB.
+        if !self.0.is_active() {
+            return Err(HandleError::Unavailable);
+        }
         self.0.window.window_handle()
     }
 }
@@ -113,6 +256,9 @@ impl HasDisplayHandle for ThreadLockedRawWindowHandleWrapper {
         // as the `raw_display_handle` method is safe. We cannot guarantee that all calls
         // of this method are correct (as it may be off the main thread on an incompatible platform),
         // and so exposing a safe method to get a [`RawDisplayHandle`] directly would be UB.
+        if !self.0.is_active() {
+            return Err(HandleError::Unavailable);
+        }
         self.0.window.display_handle()
     }
 }